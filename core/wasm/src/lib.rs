@@ -5,7 +5,7 @@
 //! - URL normalization (WHATWG + PEAC rules)
 //! - CSS/XPath selector normalization
 //! - JCS SHA-256 hash (for policy_hash)
-//! - Ed25519 JWS verification
+//! - JWS verification (Ed25519, RSA PKCS#1 v1.5, RSASSA-PSS)
 //!
 //! Design goals:
 //! - Deterministic across all runtimes (Node/Bun/Deno/CF/Vercel)
@@ -41,9 +41,8 @@ fn canonicalize_value(value: &serde_json::Value) -> Result<String, JsValue> {
                 if i > 0 {
                     result.push(',');
                 }
-                result.push('"');
-                result.push_str(key);
-                result.push_str("\":");
+                result.push_str(&escape_json_string(key));
+                result.push(':');
                 result.push_str(&canonicalize_value(val)?);
             }
 
@@ -61,36 +60,204 @@ fn canonicalize_value(value: &serde_json::Value) -> Result<String, JsValue> {
             result.push(']');
             Ok(result)
         }
-        serde_json::Value::String(s) => {
-            Ok(format!("\"{}\"", s.replace('"', "\\\"")))
-        }
-        serde_json::Value::Number(n) => Ok(n.to_string()),
+        serde_json::Value::String(s) => Ok(escape_json_string(s)),
+        serde_json::Value::Number(n) => Ok(ecma_number_to_string(
+            n.as_f64()
+                .expect("serde_json::Number always converts to f64 without the arbitrary_precision feature"),
+        )),
         serde_json::Value::Bool(b) => Ok(b.to_string()),
         serde_json::Value::Null => Ok("null".to_string()),
     }
 }
 
+/// Escape a string as a JSON string literal per RFC 8785: `"`, `\`, and
+/// the named two-character escapes (`\b`, `\f`, `\n`, `\r`, `\t`), with
+/// every other control character below `0x20` as `\u00xx`. Non-ASCII
+/// codepoints are left as raw UTF-8 rather than `\uXXXX`-escaped.
+fn escape_json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{0C}' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+/// Render an `f64` the way ECMAScript's `Number::toString` would, since
+/// JCS mandates that number serialization for round-tripping. This
+/// reuses Rust's shortest-round-trip scientific formatting for the
+/// significant digits (`s`/`k`/`n` in ECMA-262 terms) and then applies
+/// `Number::toString`'s notation rules: no trailing `.0`, `1e+21` rather
+/// than Rust's `1e21`, and full decimal expansion for exponents in
+/// `(-6, 21]`.
+fn ecma_number_to_string(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+
+    let negative = value.is_sign_negative();
+    let sci = format!("{:e}", value.abs());
+    let (mantissa, exponent) = sci.split_once('e').expect("scientific notation always contains 'e'");
+    let exponent: i32 = exponent.parse().expect("exponent is always a valid integer");
+
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    let mut out = String::new();
+    if negative {
+        out.push('-');
+    }
+
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if n > 0 && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if n > -6 && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', (-n) as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        out.push('e');
+        let unbiased_exponent = n - 1;
+        out.push(if unbiased_exponent >= 0 { '+' } else { '-' });
+        out.push_str(&unbiased_exponent.abs().to_string());
+    }
+
+    out
+}
+
+/// Query parameters with no canonical identity value, stripped (when
+/// `drop_tracking_params` is enabled) so two otherwise-identical PEAC
+/// resources don't hash differently depending on which tracking campaign
+/// referred the request.
+const TRACKING_PARAMS: &[&str] = &[
+    "utm_source",
+    "utm_medium",
+    "utm_campaign",
+    "utm_term",
+    "utm_content",
+    "gclid",
+    "fbclid",
+    "msclkid",
+    "mc_cid",
+    "mc_eid",
+];
+
+/// Sort query parameters by key (stable on duplicate keys, so repeated
+/// keys keep their relative order), always dropping empty-key parameters
+/// and optionally dropping known tracking parameters. Returns `None` when
+/// nothing is left to serialize.
+fn normalize_query(query: &str, drop_tracking_params: bool) -> Option<String> {
+    let mut pairs: Vec<(String, String)> = url::form_urlencoded::parse(query.as_bytes())
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .filter(|(key, _)| !key.is_empty())
+        .filter(|(key, _)| !drop_tracking_params || !TRACKING_PARAMS.contains(&key.as_str()))
+        .collect();
+
+    if pairs.is_empty() {
+        return None;
+    }
+
+    pairs.sort_by(|a, b| a.0.cmp(&b.0));
+
+    Some(
+        url::form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(pairs)
+            .finish(),
+    )
+}
+
+/// Uppercase the hex digits of every `%XX` percent-encoded triplet so the
+/// same byte is represented identically regardless of the case the host
+/// URL parser or the original input happened to use.
+fn normalize_percent_encoding_case(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = String::with_capacity(s.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() && bytes[i + 1].is_ascii_hexdigit() && bytes[i + 2].is_ascii_hexdigit() {
+            out.push('%');
+            out.push(char::from(bytes[i + 1]).to_ascii_uppercase());
+            out.push(char::from(bytes[i + 2]).to_ascii_uppercase());
+            i += 3;
+        } else {
+            // '%' and hex digits are single-byte ASCII, so anything that
+            // doesn't match `%XX` is either plain ASCII or part of a
+            // multi-byte UTF-8 sequence; copy it through by char boundary.
+            let ch_len = s[i..].chars().next().map(char::len_utf8).unwrap_or(1);
+            out.push_str(&s[i..i + ch_len]);
+            i += ch_len;
+        }
+    }
+
+    out
+}
+
 /// Normalize URL according to WHATWG + PEAC rules
 ///
 /// Steps:
-/// 1. Parse URL
-/// 2. Lowercase scheme and host
-/// 3. Remove default ports (80 for http, 443 for https)
-/// 4. Normalize path (remove /./, collapse /../)
-/// 5. Sort query parameters
+/// 1. Parse URL (WHATWG parsing already collapses `/./` and `/../` path
+///    segments, including percent-encoded `%2e%2e`, for any URL with a
+///    hierarchical path)
+/// 2. Lowercase scheme and host (WHATWG parsing)
+/// 3. Remove default ports (80 for http, 443 for https) (WHATWG parsing)
+/// 4. Sort query parameters by key, dropping empty-key params and
+///    (only when `drop_tracking_params` is `Some(true)`) known tracking
+///    params
+/// 5. Uppercase percent-encoding hex digits
 /// 6. Remove fragment
+///
+/// Step 4 only applies to URLs with a hierarchical path (`cannot_be_a_base()
+/// == false`); opaque identifiers like `urn:...`, `tag:...`, or
+/// `mailto:a@b.com` keep their query in its original order. WHATWG parsing
+/// likewise leaves dot segments in an opaque path as they are.
+///
+/// Steps 4-5 are fully specified here (not delegated to `url` crate
+/// defaults) so the canonical identifier is byte-identical across
+/// Node/Bun/Deno/CF/Vercel.
+///
+/// `drop_tracking_params` defaults to `false` (`None`), so callers that
+/// don't pass it keep known tracking params in the normalized URL (steps
+/// 4-5 still sort the query and uppercase percent-encodings); pass
+/// `Some(true)` to opt into stripping known tracking params.
 #[wasm_bindgen]
-pub fn normalize_url(input: &str) -> Result<String, JsValue> {
+pub fn normalize_url(input: &str, drop_tracking_params: Option<bool>) -> Result<String, JsValue> {
     let mut parsed = url::Url::parse(input)
         .map_err(|e| JsValue::from_str(&format!("URL parse error: {}", e)))?;
 
-    // Remove fragment
     parsed.set_fragment(None);
 
-    // WHATWG URL automatically lowercases scheme and host
-    // and removes default ports
+    if !parsed.cannot_be_a_base() {
+        let normalized_query = parsed
+            .query()
+            .and_then(|q| normalize_query(q, drop_tracking_params.unwrap_or(false)));
+        parsed.set_query(normalized_query.as_deref());
+    }
 
-    Ok(parsed.to_string())
+    Ok(normalize_percent_encoding_case(parsed.as_str()))
 }
 
 /// Normalize CSS/XPath selector
@@ -133,32 +300,30 @@ pub fn jcs_sha256(input: &str) -> Result<String, JsValue> {
     Ok(URL_SAFE_NO_PAD.encode(hash))
 }
 
-/// Verify Ed25519 JWS signature
-///
-/// Takes:
-/// - jws: compact JWS string (header.payload.signature)
-/// - jwk_json: Ed25519 public key in JWK format
-///
-/// Returns: true if signature is valid, false otherwise
-#[wasm_bindgen]
-pub fn verify_jws(jws: &str, jwk_json: &str) -> Result<bool, JsValue> {
-    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+/// Decode a JWS protected header and return the parsed header together with
+/// its `alg` value.
+fn parse_header_alg(protected: &str) -> Result<(serde_json::Value, String), JsValue> {
     use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 
-    // Split JWS into parts
-    let parts: Vec<&str> = jws.split('.').collect();
-    if parts.len() != 3 {
-        return Err(JsValue::from_str("Invalid JWS format"));
-    }
+    let header_bytes = URL_SAFE_NO_PAD
+        .decode(protected)
+        .map_err(|e| JsValue::from_str(&format!("Header decode error: {}", e)))?;
 
-    let header_payload = format!("{}.{}", parts[0], parts[1]);
-    let signature_bytes = URL_SAFE_NO_PAD
-        .decode(parts[2])
-        .map_err(|e| JsValue::from_str(&format!("Signature decode error: {}", e)))?;
+    let header: serde_json::Value = serde_json::from_slice(&header_bytes)
+        .map_err(|e| JsValue::from_str(&format!("Header parse error: {}", e)))?;
 
-    // Parse JWK
-    let jwk: serde_json::Value = serde_json::from_str(jwk_json)
-        .map_err(|e| JsValue::from_str(&format!("JWK parse error: {}", e)))?;
+    let alg = header["alg"]
+        .as_str()
+        .ok_or_else(|| JsValue::from_str("Missing 'alg' in protected header"))?
+        .to_string();
+
+    Ok((header, alg))
+}
+
+/// Verify an Ed25519 signature using the `x` member of an OKP JWK.
+fn verify_ed25519(jwk: &serde_json::Value, signing_input: &[u8], signature: &[u8]) -> Result<bool, JsValue> {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 
     let x = jwk["x"]
         .as_str()
@@ -176,22 +341,764 @@ pub fn verify_jws(jws: &str, jwk_json: &str) -> Result<bool, JsValue> {
     .map_err(|e| JsValue::from_str(&format!("Invalid public key: {}", e)))?;
 
     let signature = Signature::from_bytes(
-        &signature_bytes
+        &signature
             .try_into()
             .map_err(|_| JsValue::from_str("Invalid signature length"))?
     );
 
-    // Verify
-    match verifying_key.verify(header_payload.as_bytes(), &signature) {
+    match verifying_key.verify(signing_input, &signature) {
         Ok(_) => Ok(true),
         Err(_) => Ok(false),
     }
 }
 
+/// Reconstruct an RSA public key from the `n` (modulus) and `e` (exponent)
+/// members of a JWK, without requiring DER/PEM input.
+fn rsa_public_key_from_jwk(jwk: &serde_json::Value) -> Result<rsa::RsaPublicKey, JsValue> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use rsa::BigUint;
+
+    let n = jwk["n"]
+        .as_str()
+        .ok_or_else(|| JsValue::from_str("Missing 'n' in JWK"))?;
+    let e = jwk["e"]
+        .as_str()
+        .ok_or_else(|| JsValue::from_str("Missing 'e' in JWK"))?;
+
+    let n_bytes = URL_SAFE_NO_PAD
+        .decode(n)
+        .map_err(|e| JsValue::from_str(&format!("Modulus decode error: {}", e)))?;
+    let e_bytes = URL_SAFE_NO_PAD
+        .decode(e)
+        .map_err(|e| JsValue::from_str(&format!("Exponent decode error: {}", e)))?;
+
+    rsa::RsaPublicKey::new(BigUint::from_bytes_be(&n_bytes), BigUint::from_bytes_be(&e_bytes))
+        .map_err(|e| JsValue::from_str(&format!("Invalid RSA key: {}", e)))
+}
+
+/// Verify an RSA PKCS#1 v1.5 (RS*) or RSASSA-PSS (PS*) signature, selecting
+/// the SHA-2 digest from the algorithm suffix.
+fn verify_rsa(alg: &str, jwk: &serde_json::Value, signing_input: &[u8], signature: &[u8]) -> Result<bool, JsValue> {
+    use rsa::Pkcs1v15Sign;
+    use rsa::pss::Pss;
+    use sha2::{Digest, Sha256, Sha384, Sha512};
+
+    let public_key = rsa_public_key_from_jwk(jwk)?;
+
+    let verified = match alg {
+        "RS256" => public_key
+            .verify(Pkcs1v15Sign::new::<Sha256>(), &Sha256::digest(signing_input), signature)
+            .is_ok(),
+        "RS384" => public_key
+            .verify(Pkcs1v15Sign::new::<Sha384>(), &Sha384::digest(signing_input), signature)
+            .is_ok(),
+        "RS512" => public_key
+            .verify(Pkcs1v15Sign::new::<Sha512>(), &Sha512::digest(signing_input), signature)
+            .is_ok(),
+        "PS256" => public_key
+            .verify(Pss::new::<Sha256>(), &Sha256::digest(signing_input), signature)
+            .is_ok(),
+        "PS384" => public_key
+            .verify(Pss::new::<Sha384>(), &Sha384::digest(signing_input), signature)
+            .is_ok(),
+        "PS512" => public_key
+            .verify(Pss::new::<Sha512>(), &Sha512::digest(signing_input), signature)
+            .is_ok(),
+        other => return Err(JsValue::from_str(&format!("Unsupported algorithm: {}", other))),
+    };
+
+    Ok(verified)
+}
+
+/// Pure predicate behind `check_alg_permitted`, kept JsValue-free so it can
+/// be unit-tested on any target (`JsValue` construction only works on
+/// `wasm32` under a JS host).
+fn is_alg_permitted(alg: &str, allowed_algs: Option<&[String]>) -> bool {
+    if alg.is_empty() || alg.eq_ignore_ascii_case("none") {
+        return false;
+    }
+
+    match allowed_algs {
+        Some(allowed) => allowed.iter().any(|a| a == alg),
+        None => true,
+    }
+}
+
+/// Reject structurally unacceptable algorithms before any key material is
+/// touched: empty/`none` algorithms, and algorithms outside the caller's
+/// optional allow-list.
+fn check_alg_permitted(alg: &str, allowed_algs: Option<&[String]>) -> Result<(), JsValue> {
+    if alg.is_empty() || alg.eq_ignore_ascii_case("none") {
+        return Err(JsValue::from_str("Algorithm 'none' is not permitted"));
+    }
+
+    if !is_alg_permitted(alg, allowed_algs) {
+        return Err(JsValue::from_str(&format!(
+            "Algorithm '{}' is not in the caller's allow-list",
+            alg
+        )));
+    }
+
+    Ok(())
+}
+
+/// Pure predicate behind `check_alg_matches_jwk`, kept JsValue-free so it
+/// can be unit-tested on any target.
+fn alg_compatible_with_jwk(alg: &str, kty: &str, crv: &str) -> bool {
+    match alg {
+        "EdDSA" => kty == "OKP" && crv == "Ed25519",
+        "RS256" | "RS384" | "RS512" | "PS256" | "PS384" | "PS512" => kty == "RSA",
+        _ => false,
+    }
+}
+
+/// Cross-check the header's `alg` against the JWK's `kty`/`crv` so a token
+/// cannot substitute a different algorithm than the one the key was
+/// published for (e.g. presenting an RSA key but claiming `EdDSA`).
+fn check_alg_matches_jwk(alg: &str, jwk: &serde_json::Value) -> Result<(), JsValue> {
+    let kty = jwk["kty"].as_str().unwrap_or("");
+    let crv = jwk["crv"].as_str().unwrap_or("");
+
+    if !alg_compatible_with_jwk(alg, kty, crv) {
+        return Err(JsValue::from_str(&format!(
+            "Algorithm '{}' is not compatible with JWK kty '{}' / crv '{}'",
+            alg, kty, crv
+        )));
+    }
+
+    Ok(())
+}
+
+/// Dispatch signature verification on the protected header's `alg`.
+fn verify_signature(alg: &str, jwk: &serde_json::Value, signing_input: &[u8], signature: &[u8]) -> Result<bool, JsValue> {
+    match alg {
+        "EdDSA" => verify_ed25519(jwk, signing_input, signature),
+        "RS256" | "RS384" | "RS512" | "PS256" | "PS384" | "PS512" => verify_rsa(alg, jwk, signing_input, signature),
+        other => Err(JsValue::from_str(&format!("Unsupported algorithm: {}", other))),
+    }
+}
+
+/// A parsed-and-validated compact JWS, ready for key lookup.
+struct DecodedJws {
+    header: serde_json::Value,
+    alg: String,
+    signing_input: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+/// Split, decode and structurally validate a compact JWS: checks the part
+/// count, decodes the protected header, and enforces `alg` rejection/the
+/// allow-list before any key material is consulted.
+fn decode_jws(jws: &str, allowed_algs_json: Option<String>) -> Result<DecodedJws, JsValue> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+    let parts: Vec<&str> = jws.split('.').collect();
+    if parts.len() != 3 {
+        return Err(JsValue::from_str("Invalid JWS format"));
+    }
+
+    let (header, alg) = parse_header_alg(parts[0])?;
+
+    let allowed_algs = allowed_algs_json
+        .map(|json| {
+            serde_json::from_str::<Vec<String>>(&json)
+                .map_err(|e| JsValue::from_str(&format!("Invalid allowed algorithm list: {}", e)))
+        })
+        .transpose()?;
+
+    check_alg_permitted(&alg, allowed_algs.as_deref())?;
+
+    let signing_input = format!("{}.{}", parts[0], parts[1]).into_bytes();
+    let signature = URL_SAFE_NO_PAD
+        .decode(parts[2])
+        .map_err(|e| JsValue::from_str(&format!("Signature decode error: {}", e)))?;
+
+    Ok(DecodedJws { header, alg, signing_input, signature })
+}
+
+/// Verify a JWS signature.
+///
+/// Takes:
+/// - jws: compact JWS string (header.payload.signature)
+/// - jwk_json: public key in JWK format (Ed25519 `OKP` or `RSA`)
+/// - allowed_algs_json: optional JSON array of acceptable `alg` values
+///   (e.g. `["RS256","PS256"]`) that a relying party can pin; `None` skips
+///   this check
+///
+/// Dispatches on the protected header's `alg` and the JWK's `kty`:
+/// - `EdDSA` against an `OKP`/`Ed25519` key
+/// - `RS256`/`RS384`/`RS512` (PKCS#1 v1.5) and `PS256`/`PS384`/`PS512`
+///   (RSASSA-PSS, MGF1+SHA) against an `RSA` key
+///
+/// `alg: "none"` (or an empty `alg`) is always rejected, and the header's
+/// `alg` must be compatible with the supplied JWK's `kty`/`crv` before any
+/// signature bytes are checked, preventing algorithm-substitution attacks.
+///
+/// Returns `Err` when the JWS/JWK is structurally invalid or the algorithm
+/// is rejected; returns `Ok(false)` only when the signature itself fails to
+/// verify.
+#[wasm_bindgen]
+pub fn verify_jws(jws: &str, jwk_json: &str, allowed_algs_json: Option<String>) -> Result<bool, JsValue> {
+    let parsed = decode_jws(jws, allowed_algs_json)?;
+
+    let jwk: serde_json::Value = serde_json::from_str(jwk_json)
+        .map_err(|e| JsValue::from_str(&format!("JWK parse error: {}", e)))?;
+
+    check_alg_matches_jwk(&parsed.alg, &jwk)?;
+
+    verify_signature(&parsed.alg, &jwk, &parsed.signing_input, &parsed.signature)
+}
+
+/// Select candidate JWKs from a JWK Set for the given header/`alg`: an
+/// exact `kid` match when the header carries one, otherwise every key
+/// whose `use`/`alg` don't rule it out and whose `kty`/`crv` is compatible
+/// with `alg`.
+fn select_jwks_candidates<'a>(
+    jwks: &'a serde_json::Value,
+    header: &serde_json::Value,
+    alg: &str,
+) -> Result<Vec<&'a serde_json::Value>, JsValue> {
+    let keys = jwks["keys"]
+        .as_array()
+        .ok_or_else(|| JsValue::from_str("Missing 'keys' array in JWK Set"))?;
+
+    let kid = header["kid"].as_str();
+
+    let candidates: Vec<&serde_json::Value> = if let Some(kid) = kid {
+        keys.iter()
+            .filter(|key| key["kid"].as_str() == Some(kid))
+            .filter(|key| check_alg_matches_jwk(alg, key).is_ok())
+            .collect()
+    } else {
+        keys.iter()
+            .filter(|key| key["alg"].as_str().is_none_or(|key_alg| key_alg == alg))
+            .filter(|key| key["use"].as_str().is_none_or(|u| u == "sig"))
+            .filter(|key| check_alg_matches_jwk(alg, key).is_ok())
+            .collect()
+    };
+
+    if candidates.is_empty() {
+        return Err(JsValue::from_str("No matching key found in JWK Set"));
+    }
+
+    Ok(candidates)
+}
+
+/// Verify a JWS against a full JWK Set (`{"keys":[...]}`) instead of a
+/// single JWK, selecting the key by the protected header's `kid`. If no
+/// `kid` is present, every key compatible with the header's `alg` is
+/// tried in turn, matching how relying parties consume rotating issuer
+/// keys published at a JWKS endpoint.
+///
+/// Takes:
+/// - jws: compact JWS string (header.payload.signature)
+/// - jwks_json: a JWK Set (`{"keys":[...]}`)
+/// - allowed_algs_json: optional JSON array of acceptable `alg` values
+///
+/// Returns `Ok(true)` as soon as any candidate key verifies the signature,
+/// `Ok(false)` if every candidate fails, and `Err` for structural errors.
+#[wasm_bindgen]
+pub fn verify_jws_jwks(jws: &str, jwks_json: &str, allowed_algs_json: Option<String>) -> Result<bool, JsValue> {
+    let parsed = decode_jws(jws, allowed_algs_json)?;
+
+    let jwks: serde_json::Value = serde_json::from_str(jwks_json)
+        .map_err(|e| JsValue::from_str(&format!("JWK Set parse error: {}", e)))?;
+
+    let candidates = select_jwks_candidates(&jwks, &parsed.header, &parsed.alg)?;
+
+    for jwk in candidates {
+        if verify_signature(&parsed.alg, jwk, &parsed.signing_input, &parsed.signature)? {
+            return Ok(true);
+        }
+    }
+
+    Ok(false)
+}
+
+/// Build an Ed25519 signing key from the `d` (private scalar) member of an
+/// `OKP` JWK.
+fn ed25519_signing_key_from_jwk(jwk: &serde_json::Value) -> Result<ed25519_dalek::SigningKey, JsValue> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+    let d = jwk["d"]
+        .as_str()
+        .ok_or_else(|| JsValue::from_str("Missing 'd' in JWK"))?;
+
+    let seed: [u8; 32] = URL_SAFE_NO_PAD
+        .decode(d)
+        .map_err(|e| JsValue::from_str(&format!("Private key decode error: {}", e)))?
+        .try_into()
+        .map_err(|_| JsValue::from_str("Invalid private key length"))?;
+
+    Ok(ed25519_dalek::SigningKey::from_bytes(&seed))
+}
+
+fn sign_ed25519(jwk: &serde_json::Value, signing_input: &[u8]) -> Result<Vec<u8>, JsValue> {
+    use ed25519_dalek::Signer;
+
+    let signing_key = ed25519_signing_key_from_jwk(jwk)?;
+    Ok(signing_key.sign(signing_input).to_bytes().to_vec())
+}
+
+/// Reconstruct an RSA private key from the `n`/`e`/`d`/`p`/`q` members of
+/// a JWK (the two-prime form; CRT parameters `dp`/`dq`/`qi` are derived by
+/// the `rsa` crate rather than read from the JWK).
+fn rsa_private_key_from_jwk(jwk: &serde_json::Value) -> Result<rsa::RsaPrivateKey, JsValue> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+    use rsa::BigUint;
+
+    let component = |field: &str| -> Result<BigUint, JsValue> {
+        let value = jwk[field]
+            .as_str()
+            .ok_or_else(|| JsValue::from_str(&format!("Missing '{}' in JWK", field)))?;
+        let bytes = URL_SAFE_NO_PAD
+            .decode(value)
+            .map_err(|e| JsValue::from_str(&format!("'{}' decode error: {}", field, e)))?;
+        Ok(BigUint::from_bytes_be(&bytes))
+    };
+
+    let n = component("n")?;
+    let e = component("e")?;
+    let d = component("d")?;
+    let p = component("p")?;
+    let q = component("q")?;
+
+    rsa::RsaPrivateKey::from_components(n, e, d, vec![p, q])
+        .map_err(|e| JsValue::from_str(&format!("Invalid RSA private key: {}", e)))
+}
+
+/// RS* (PKCS#1 v1.5) signs deterministically via `sign` (no RNG, no
+/// blinding) to match this module's "deterministic across all runtimes /
+/// edge-safe" design goals. PS* (RSASSA-PSS) has no such option: the
+/// scheme is only secure with a random salt, so it always needs an RNG
+/// (`OsRng`) regardless of blinding. On `wasm32-unknown-unknown` that
+/// means the eventual Cargo.toml must enable a `getrandom` backend (e.g.
+/// its `js` feature) or PS* signing will fail at runtime; RS*/EdDSA are
+/// unaffected.
+fn sign_rsa(alg: &str, jwk: &serde_json::Value, signing_input: &[u8]) -> Result<Vec<u8>, JsValue> {
+    use rsa::Pkcs1v15Sign;
+    use rsa::pss::Pss;
+    use sha2::{Digest, Sha256, Sha384, Sha512};
+
+    let private_key = rsa_private_key_from_jwk(jwk)?;
+
+    match alg {
+        "RS256" => private_key.sign(Pkcs1v15Sign::new::<Sha256>(), &Sha256::digest(signing_input)),
+        "RS384" => private_key.sign(Pkcs1v15Sign::new::<Sha384>(), &Sha384::digest(signing_input)),
+        "RS512" => private_key.sign(Pkcs1v15Sign::new::<Sha512>(), &Sha512::digest(signing_input)),
+        "PS256" => private_key.sign_with_rng(&mut rand::rngs::OsRng, Pss::new::<Sha256>(), &Sha256::digest(signing_input)),
+        "PS384" => private_key.sign_with_rng(&mut rand::rngs::OsRng, Pss::new::<Sha384>(), &Sha384::digest(signing_input)),
+        "PS512" => private_key.sign_with_rng(&mut rand::rngs::OsRng, Pss::new::<Sha512>(), &Sha512::digest(signing_input)),
+        other => return Err(JsValue::from_str(&format!("Unsupported algorithm: {}", other))),
+    }
+    .map_err(|e| JsValue::from_str(&format!("Signing error: {}", e)))
+}
+
+/// Sign a JSON payload as a compact JWS.
+///
+/// Takes:
+/// - payload: JSON payload to sign; canonicalized the same way as
+///   `jcs_sha256` before being embedded, so the bytes that get signed
+///   match the deterministic PEAC `policy_hash` representation
+/// - jwk_json: private key material in JWK format (Ed25519 `d`, or RSA
+///   `n`/`e`/`d`/`p`/`q`)
+/// - alg: `EdDSA`, `RS256`/`RS384`/`RS512`, or `PS256`/`PS384`/`PS512`
+///
+/// Returns the compact JWS (`header.payload.signature`).
+#[wasm_bindgen]
+pub fn sign_jws(payload: &str, jwk_json: &str, alg: &str) -> Result<String, JsValue> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+    let canonical_payload = canonicalize_json(payload)?;
+
+    let jwk: serde_json::Value = serde_json::from_str(jwk_json)
+        .map_err(|e| JsValue::from_str(&format!("JWK parse error: {}", e)))?;
+
+    check_alg_matches_jwk(alg, &jwk)?;
+
+    let header = format!(r#"{{"alg":"{}","typ":"JWT"}}"#, alg);
+    let header_b64 = URL_SAFE_NO_PAD.encode(header.as_bytes());
+    let payload_b64 = URL_SAFE_NO_PAD.encode(canonical_payload.as_bytes());
+    let signing_input = format!("{}.{}", header_b64, payload_b64);
+
+    let signature = match alg {
+        "EdDSA" => sign_ed25519(&jwk, signing_input.as_bytes())?,
+        "RS256" | "RS384" | "RS512" | "PS256" | "PS384" | "PS512" => {
+            sign_rsa(alg, &jwk, signing_input.as_bytes())?
+        }
+        other => return Err(JsValue::from_str(&format!("Unsupported algorithm: {}", other))),
+    };
+
+    let signature_b64 = URL_SAFE_NO_PAD.encode(signature);
+
+    Ok(format!("{}.{}", signing_input, signature_b64))
+}
+
+/// Decode the payload segment of a compact JWS as JSON, without touching
+/// the signature.
+fn decode_jws_payload(jws: &str) -> Result<serde_json::Value, JsValue> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+    let parts: Vec<&str> = jws.split('.').collect();
+    if parts.len() != 3 {
+        return Err(JsValue::from_str("Invalid JWS format"));
+    }
+
+    let payload_bytes = URL_SAFE_NO_PAD
+        .decode(parts[1])
+        .map_err(|e| JsValue::from_str(&format!("Payload decode error: {}", e)))?;
+
+    serde_json::from_slice(&payload_bytes)
+        .map_err(|e| JsValue::from_str(&format!("Payload parse error: {}", e)))
+}
+
+/// Check whether the payload's `aud` (string or array form) contains any
+/// of `expected`. An empty `expected` list means the caller isn't
+/// checking audience, so every payload passes.
+fn audience_matches(payload: &serde_json::Value, expected: &[String]) -> bool {
+    if expected.is_empty() {
+        return true;
+    }
+
+    match &payload["aud"] {
+        serde_json::Value::String(aud) => expected.iter().any(|e| e == aud),
+        serde_json::Value::Array(auds) => auds
+            .iter()
+            .filter_map(|a| a.as_str())
+            .any(|aud| expected.iter().any(|e| e == aud)),
+        _ => false,
+    }
+}
+
+/// Evaluate the registered claims (`exp`/`nbf`/`iat`/`aud`/`iss`) of an
+/// already-signature-verified payload.
+///
+/// `options_json` is a JSON object:
+/// `{"now": <unix seconds>, "leeway": <seconds, default 0>,
+///   "aud": [<strings>], "iss": [<strings>]}`. `now` is required since the
+/// WASM core has no clock access; `aud`/`iss` default to empty (not
+/// checked).
+///
+/// Returns a JSON string `{"valid":bool,"reason":string|null}` where
+/// `reason` is one of `"expired"`, `"not_yet_valid"`, `"audience"`, or
+/// `"issuer"` when `valid` is `false`, so the caller can log the precise
+/// cause rather than a single opaque failure. (`"signature"` is produced
+/// by the `validate_jws_claims*` callers, never by this function.)
+fn evaluate_claims(payload: &serde_json::Value, options_json: &str) -> Result<String, JsValue> {
+    let options: serde_json::Value = serde_json::from_str(options_json)
+        .map_err(|e| JsValue::from_str(&format!("Options parse error: {}", e)))?;
+
+    let now = options["now"]
+        .as_i64()
+        .ok_or_else(|| JsValue::from_str("Missing 'now' in options"))?;
+    let leeway = options["leeway"].as_i64().unwrap_or(0);
+
+    let expected_aud: Vec<String> = options["aud"]
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+    let expected_iss: Vec<String> = options["iss"]
+        .as_array()
+        .map(|arr| arr.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+
+    let expired = payload["exp"].as_i64().is_some_and(|exp| now - leeway >= exp);
+    let not_yet_valid = payload["nbf"].as_i64().is_some_and(|nbf| now + leeway < nbf)
+        || payload["iat"].as_i64().is_some_and(|iat| now + leeway < iat);
+    let audience_ok = audience_matches(payload, &expected_aud);
+    let issuer_ok = expected_iss.is_empty()
+        || payload["iss"]
+            .as_str()
+            .is_some_and(|iss| expected_iss.iter().any(|e| e == iss));
+
+    let reason = if expired {
+        Some("expired")
+    } else if not_yet_valid {
+        Some("not_yet_valid")
+    } else if !audience_ok {
+        Some("audience")
+    } else if !issuer_ok {
+        Some("issuer")
+    } else {
+        None
+    };
+
+    Ok(match reason {
+        Some(reason) => format!(r#"{{"valid":false,"reason":"{}"}}"#, reason),
+        None => r#"{"valid":true,"reason":null}"#.to_string(),
+    })
+}
+
+/// Verify a JWS against a single JWK, then validate its registered claims.
+///
+/// Claims are never evaluated without first checking the signature: a
+/// forged JWS with attacker-chosen `exp`/`aud`/`iss` fails at the
+/// signature step instead of reaching the claims check, so callers can't
+/// accidentally treat an unverified token as trusted.
+///
+/// Takes:
+/// - jws: compact JWS string (header.payload.signature)
+/// - jwk_json: public key in JWK format, as accepted by `verify_jws`
+/// - options_json: see `evaluate_claims` for the schema
+/// - allowed_algs_json: optional JSON array of acceptable `alg` values
+///
+/// Returns a JSON string `{"valid":bool,"reason":string|null}`; `reason`
+/// is `"signature"` when the signature itself doesn't verify.
+#[wasm_bindgen]
+pub fn validate_jws_claims(
+    jws: &str,
+    jwk_json: &str,
+    options_json: &str,
+    allowed_algs_json: Option<String>,
+) -> Result<String, JsValue> {
+    let parsed = decode_jws(jws, allowed_algs_json)?;
+
+    let jwk: serde_json::Value = serde_json::from_str(jwk_json)
+        .map_err(|e| JsValue::from_str(&format!("JWK parse error: {}", e)))?;
+
+    check_alg_matches_jwk(&parsed.alg, &jwk)?;
+
+    if !verify_signature(&parsed.alg, &jwk, &parsed.signing_input, &parsed.signature)? {
+        return Ok(r#"{"valid":false,"reason":"signature"}"#.to_string());
+    }
+
+    let payload = decode_jws_payload(jws)?;
+    evaluate_claims(&payload, options_json)
+}
+
+/// Verify a JWS against a JWK Set, then validate its registered claims.
+/// The JWK-Set counterpart to `validate_jws_claims`, mirroring how
+/// `verify_jws_jwks` complements `verify_jws`.
+///
+/// Takes:
+/// - jws: compact JWS string (header.payload.signature)
+/// - jwks_json: a JWK Set (`{"keys":[...]}`)
+/// - options_json: see `evaluate_claims` for the schema
+/// - allowed_algs_json: optional JSON array of acceptable `alg` values
+///
+/// Returns a JSON string `{"valid":bool,"reason":string|null}`; `reason`
+/// is `"signature"` when no candidate key verifies the signature.
+#[wasm_bindgen]
+pub fn validate_jws_claims_jwks(
+    jws: &str,
+    jwks_json: &str,
+    options_json: &str,
+    allowed_algs_json: Option<String>,
+) -> Result<String, JsValue> {
+    let parsed = decode_jws(jws, allowed_algs_json)?;
+
+    let jwks: serde_json::Value = serde_json::from_str(jwks_json)
+        .map_err(|e| JsValue::from_str(&format!("JWK Set parse error: {}", e)))?;
+
+    let candidates = select_jwks_candidates(&jwks, &parsed.header, &parsed.alg)?;
+
+    let mut signature_valid = false;
+    for jwk in candidates {
+        if verify_signature(&parsed.alg, jwk, &parsed.signing_input, &parsed.signature)? {
+            signature_valid = true;
+            break;
+        }
+    }
+
+    if !signature_valid {
+        return Ok(r#"{"valid":false,"reason":"signature"}"#.to_string());
+    }
+
+    let payload = decode_jws_payload(jws)?;
+    evaluate_claims(&payload, options_json)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Fixed Ed25519 keypair (not for production use) shared by the JWS
+    /// tests below.
+    fn ed25519_test_keys() -> (serde_json::Value, serde_json::Value) {
+        ed25519_test_keys_from_seed(9)
+    }
+
+    fn ed25519_test_keys_from_seed(seed_byte: u8) -> (serde_json::Value, serde_json::Value) {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+        use ed25519_dalek::SigningKey;
+
+        let seed = [seed_byte; 32];
+        let signing_key = SigningKey::from_bytes(&seed);
+        let verifying_key = signing_key.verifying_key();
+        let d = URL_SAFE_NO_PAD.encode(seed);
+        let x = URL_SAFE_NO_PAD.encode(verifying_key.to_bytes());
+
+        (
+            serde_json::json!({"kty": "OKP", "crv": "Ed25519", "d": d, "x": x}),
+            serde_json::json!({"kty": "OKP", "crv": "Ed25519", "x": x}),
+        )
+    }
+
+    #[test]
+    fn test_validate_jws_claims_valid() {
+        let (priv_jwk, pub_jwk) = ed25519_test_keys();
+        let jws = sign_jws(r#"{"exp":9999999999}"#, &priv_jwk.to_string(), "EdDSA").unwrap();
+        let options = serde_json::json!({"now": 0}).to_string();
+
+        let result = validate_jws_claims(&jws, &pub_jwk.to_string(), &options, None).unwrap();
+        assert_eq!(result, r#"{"valid":true,"reason":null}"#);
+    }
+
+    #[test]
+    fn test_validate_jws_claims_rejects_forged_signature() {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+        let (priv_jwk, pub_jwk) = ed25519_test_keys();
+        let jws = sign_jws(r#"{"exp":9999999999}"#, &priv_jwk.to_string(), "EdDSA").unwrap();
+
+        let parts: Vec<&str> = jws.rsplitn(2, '.').collect();
+        let mut sig_bytes = URL_SAFE_NO_PAD.decode(parts[0]).unwrap();
+        sig_bytes[0] ^= 0xff;
+        let tampered_sig = URL_SAFE_NO_PAD.encode(sig_bytes);
+        let forged = format!("{}.{}", parts[1], tampered_sig);
+
+        let options = serde_json::json!({"now": 0}).to_string();
+        let result = validate_jws_claims(&forged, &pub_jwk.to_string(), &options, None).unwrap();
+        assert_eq!(result, r#"{"valid":false,"reason":"signature"}"#);
+    }
+
+    #[test]
+    fn test_validate_jws_claims_jwks_valid() {
+        let (priv_jwk, pub_jwk) = ed25519_test_keys();
+        let jws = sign_jws(r#"{"exp":9999999999}"#, &priv_jwk.to_string(), "EdDSA").unwrap();
+        let jwks = serde_json::json!({"keys": [pub_jwk]}).to_string();
+        let options = serde_json::json!({"now": 0}).to_string();
+
+        let result = validate_jws_claims_jwks(&jws, &jwks, &options, None).unwrap();
+        assert_eq!(result, r#"{"valid":true,"reason":null}"#);
+    }
+
+    fn rsa_test_jwk_pair() -> (serde_json::Value, serde_json::Value) {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+        use rsa::traits::{PrivateKeyParts, PublicKeyParts};
+        use rsa::RsaPrivateKey;
+
+        let private_key = RsaPrivateKey::new(&mut rand::rngs::OsRng, 1024).unwrap();
+        let primes = private_key.primes();
+        let n = URL_SAFE_NO_PAD.encode(private_key.n().to_bytes_be());
+        let e = URL_SAFE_NO_PAD.encode(private_key.e().to_bytes_be());
+        let d = URL_SAFE_NO_PAD.encode(private_key.d().to_bytes_be());
+        let p = URL_SAFE_NO_PAD.encode(primes[0].to_bytes_be());
+        let q = URL_SAFE_NO_PAD.encode(primes[1].to_bytes_be());
+
+        (
+            serde_json::json!({"kty": "RSA", "n": &n, "e": &e, "d": d, "p": p, "q": q}),
+            serde_json::json!({"kty": "RSA", "n": n, "e": e}),
+        )
+    }
+
+    #[test]
+    fn test_rsa_round_trip() {
+        let (priv_jwk, pub_jwk) = rsa_test_jwk_pair();
+        let jws = sign_jws(r#"{"hello":"rsa"}"#, &priv_jwk.to_string(), "RS256").unwrap();
+        assert!(verify_jws(&jws, &pub_jwk.to_string(), None).unwrap());
+    }
+
+    #[test]
+    fn test_sign_rsa_rs256_is_deterministic() {
+        // RS256 signs with `sign` (no RNG/blinding) so the same payload
+        // always produces byte-identical signatures, matching this
+        // module's "deterministic across all runtimes" design goal.
+        let (priv_jwk, _) = rsa_test_jwk_pair();
+        let jws1 = sign_jws(r#"{"hello":"rsa"}"#, &priv_jwk.to_string(), "RS256").unwrap();
+        let jws2 = sign_jws(r#"{"hello":"rsa"}"#, &priv_jwk.to_string(), "RS256").unwrap();
+        assert_eq!(jws1, jws2);
+    }
+
+    #[test]
+    fn test_verify_jws_rsa_pss() {
+        let (priv_jwk, pub_jwk) = rsa_test_jwk_pair();
+        let jws = sign_jws(r#"{"hello":"pss"}"#, &priv_jwk.to_string(), "PS256").unwrap();
+        assert!(verify_jws(&jws, &pub_jwk.to_string(), None).unwrap());
+    }
+
+    #[test]
+    fn test_verify_jws_rsa_wrong_key_rejected() {
+        let (priv_jwk, _) = rsa_test_jwk_pair();
+        let (_, other_pub_jwk) = rsa_test_jwk_pair();
+        let jws = sign_jws(r#"{"hello":"rsa"}"#, &priv_jwk.to_string(), "RS256").unwrap();
+        assert!(!verify_jws(&jws, &other_pub_jwk.to_string(), None).unwrap());
+    }
+
+    #[test]
+    fn test_alg_permitted_rejects_none() {
+        // Exercises the pure predicate behind check_alg_permitted: an
+        // `alg` of "none" (any case) or an empty string must never be
+        // permitted, regardless of an allow-list.
+        assert!(!is_alg_permitted("none", None));
+        assert!(!is_alg_permitted("NONE", None));
+        assert!(!is_alg_permitted("", None));
+        assert!(is_alg_permitted("EdDSA", None));
+    }
+
+    #[test]
+    fn test_alg_permitted_honors_allow_list() {
+        let allowed = vec!["RS256".to_string(), "PS256".to_string()];
+        assert!(is_alg_permitted("RS256", Some(&allowed)));
+        assert!(!is_alg_permitted("EdDSA", Some(&allowed)));
+    }
+
+    #[test]
+    fn test_alg_compatible_with_jwk_rejects_substitution() {
+        // A token claiming EdDSA must not be accepted against an RSA key,
+        // and vice versa - the core algorithm-substitution defense.
+        assert!(alg_compatible_with_jwk("EdDSA", "OKP", "Ed25519"));
+        assert!(!alg_compatible_with_jwk("EdDSA", "RSA", ""));
+        assert!(alg_compatible_with_jwk("RS256", "RSA", ""));
+        assert!(!alg_compatible_with_jwk("RS256", "OKP", "Ed25519"));
+    }
+
+    /// Build a compact JWS with an explicit protected header (so a `kid`
+    /// can be included), the way `sign_jws` does internally.
+    fn sign_jws_with_header(header_json: &str, payload: &str, priv_jwk: &serde_json::Value) -> String {
+        use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+        let canonical_payload = canonicalize_json(payload).unwrap();
+        let header_b64 = URL_SAFE_NO_PAD.encode(header_json.as_bytes());
+        let payload_b64 = URL_SAFE_NO_PAD.encode(canonical_payload.as_bytes());
+        let signing_input = format!("{}.{}", header_b64, payload_b64);
+        let signature = sign_ed25519(priv_jwk, signing_input.as_bytes()).unwrap();
+        let signature_b64 = URL_SAFE_NO_PAD.encode(signature);
+
+        format!("{}.{}", signing_input, signature_b64)
+    }
+
+    #[test]
+    fn test_verify_jws_jwks_selects_by_kid() {
+        // A JWKS mixing an RSA key and an Ed25519 key: selecting by `kid`
+        // must pick the right key without ever evaluating alg/kty
+        // compatibility against the non-matching one.
+        let (priv_a, mut pub_a) = ed25519_test_keys();
+        pub_a["kid"] = serde_json::json!("key-a");
+        let (_, mut pub_b) = rsa_test_jwk_pair();
+        pub_b["kid"] = serde_json::json!("key-b");
+
+        let jwks = serde_json::json!({"keys": [pub_a, pub_b]}).to_string();
+        let header = r#"{"alg":"EdDSA","typ":"JWT","kid":"key-a"}"#;
+        let jws = sign_jws_with_header(header, r#"{"hello":"world"}"#, &priv_a);
+
+        assert!(verify_jws_jwks(&jws, &jwks, None).unwrap());
+    }
+
+    #[test]
+    fn test_verify_jws_jwks_falls_back_without_kid() {
+        // With no `kid` in the header, every alg/use-compatible key in the
+        // set is tried, so the matching key is still found even when it's
+        // not first in the list.
+        let (_, other_pub) = ed25519_test_keys_from_seed(1);
+        let (priv_jwk, pub_jwk) = ed25519_test_keys_from_seed(2);
+
+        let jwks = serde_json::json!({"keys": [other_pub, pub_jwk]}).to_string();
+        let jws = sign_jws(r#"{"hello":"world"}"#, &priv_jwk.to_string(), "EdDSA").unwrap();
+
+        assert!(verify_jws_jwks(&jws, &jwks, None).unwrap());
+    }
+
     #[test]
     fn test_canonicalize_json() {
         let input = r#"{"z":1,"a":2,"m":{"c":3,"b":4}}"#;
@@ -199,6 +1106,40 @@ mod tests {
         assert_eq!(canonicalize_json(input).unwrap(), expected);
     }
 
+    #[test]
+    fn test_canonicalize_json_numbers() {
+        // RFC 8785 ties number formatting to ECMA-262 Number::toString:
+        // no trailing ".0", shortest round-trip digits, and `e+`/`e-`
+        // exponential notation outside [1e-6, 1e21).
+        let cases = [
+            (r#"{"a":100}"#, r#"{"a":100}"#),
+            (r#"{"a":100.0}"#, r#"{"a":100}"#),
+            (r#"{"a":1.5}"#, r#"{"a":1.5}"#),
+            (r#"{"a":-42}"#, r#"{"a":-42}"#),
+            (r#"{"a":0.0001}"#, r#"{"a":0.0001}"#),
+            (r#"{"a":1e21}"#, r#"{"a":1e+21}"#),
+            (r#"{"a":1e-7}"#, r#"{"a":1e-7}"#),
+        ];
+
+        for (input, expected) in cases {
+            assert_eq!(canonicalize_json(input).unwrap(), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_json_escapes_strings_and_keys() {
+        let input = "{\"a\\\"b\":\"x\\\"y\\nz\\t!\"}";
+        let expected = r#"{"a\"b":"x\"y\nz\t!"}"#;
+        assert_eq!(canonicalize_json(input).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_canonicalize_json_escapes_control_characters() {
+        let input = r#"{"a":"\u0001"}"#;
+        let expected = r#"{"a":"\u0001"}"#;
+        assert_eq!(canonicalize_json(input).unwrap(), expected);
+    }
+
     #[test]
     fn test_jcs_sha256() {
         let input = r#"{"z":1,"a":2}"#;
@@ -215,10 +1156,40 @@ mod tests {
     #[test]
     fn test_normalize_url() {
         let input = "https://example.com:443/path?b=2&a=1#fragment";
-        let normalized = normalize_url(input).unwrap();
+        let normalized = normalize_url(input, None).unwrap();
 
         // Should remove :443, fragment
         assert!(!normalized.contains(":443"));
         assert!(!normalized.contains("#fragment"));
     }
+
+    #[test]
+    fn test_normalize_url_opaque_passthrough() {
+        // cannot-be-a-base URLs (urn:, mailto:, tag:) have no filesystem
+        // path; dot-segment collapse must not mangle the opaque identifier.
+        assert_eq!(normalize_url("urn:a/b/..", None).unwrap(), "urn:a/b/..");
+        assert_eq!(
+            normalize_url("mailto:a@b.com?subject=hi", None).unwrap(),
+            "mailto:a@b.com?subject=hi"
+        );
+    }
+
+    #[test]
+    fn test_normalize_url_tracking_params_default_preserved() {
+        // Dropping tracking params changes the hashed identifier, so it
+        // must be opt-in: `None` and `Some(false)` both keep them.
+        let input = "https://example.com/?utm_source=x&a=1";
+        assert_eq!(
+            normalize_url(input, None).unwrap(),
+            "https://example.com/?a=1&utm_source=x"
+        );
+        assert_eq!(
+            normalize_url(input, Some(false)).unwrap(),
+            "https://example.com/?a=1&utm_source=x"
+        );
+        assert_eq!(
+            normalize_url(input, Some(true)).unwrap(),
+            "https://example.com/?a=1"
+        );
+    }
 }